@@ -0,0 +1,256 @@
+//! `sumhash` computes the Algorand sumhash512 digest of one or more files, or
+//! of stdin when no path is given (or a path is `-`), streaming the data
+//! through `CoreWrapper<AlgorandSumhash512Core>` in fixed-size chunks rather
+//! than buffering whole files. Output can be printed as hex (the default) or
+//! base64 via `--encoding`.
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Read},
+    process::ExitCode,
+};
+
+use data_encoding::{BASE64, HEXLOWER};
+use digest::{core_api::CoreWrapper, FixedOutput, Update};
+use sumhash::sumhash512core::{AlgorandSumhash512Core, Sumhash512Core, DIGEST_BLOCK_SIZE};
+use sumhash::util::fixed_time_eq;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The text encoding used to print (and, in `--check` mode, parse) digests.
+#[derive(Clone, Copy)]
+enum Encoding {
+    Hex,
+    Base64,
+}
+
+impl Encoding {
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Hex => HEXLOWER.encode(bytes),
+            Encoding::Base64 => BASE64.encode(bytes),
+        }
+    }
+
+    fn decode(self, s: &str) -> Result<Vec<u8>, data_encoding::DecodeError> {
+        match self {
+            Encoding::Hex => HEXLOWER.decode(s.as_bytes()),
+            Encoding::Base64 => BASE64.decode(s.as_bytes()),
+        }
+    }
+}
+
+struct Args {
+    salt: Option<[u8; DIGEST_BLOCK_SIZE]>,
+    check: bool,
+    encoding: Encoding,
+    paths: Vec<String>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut salt = None;
+    let mut check = false;
+    let mut encoding = Encoding::Hex;
+    let mut paths = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--salt" => {
+                let hex_salt = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--salt requires a value"))?;
+                let bytes = hex::decode(&hex_salt)?;
+                if bytes.len() != DIGEST_BLOCK_SIZE {
+                    anyhow::bail!(
+                        "salt must be {} bytes, got {}",
+                        DIGEST_BLOCK_SIZE,
+                        bytes.len()
+                    );
+                }
+                let mut s = [0u8; DIGEST_BLOCK_SIZE];
+                s.copy_from_slice(&bytes);
+                salt = Some(s);
+            }
+            "--check" => check = true,
+            "--encoding" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--encoding requires a value"))?;
+                encoding = match value.as_str() {
+                    "hex" => Encoding::Hex,
+                    "base64" => Encoding::Base64,
+                    other => anyhow::bail!("unknown encoding {:?}, want hex or base64", other),
+                };
+            }
+            other => paths.push(other.to_string()),
+        }
+    }
+
+    Ok(Args {
+        salt,
+        check,
+        encoding,
+        paths,
+    })
+}
+
+/// Streams `r` through the sumhash512 compressor in fixed-size chunks and
+/// returns the resulting digest.
+fn hash_reader<R: Read>(mut r: R, salt: Option<[u8; DIGEST_BLOCK_SIZE]>) -> io::Result<[u8; 64]> {
+    let mut buf = [0u8; CHUNK_SIZE];
+    let digest = match salt {
+        Some(salt) => {
+            let mut h = CoreWrapper::from_core(Sumhash512Core::new_with_salt(salt));
+            loop {
+                let n = r.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                h.update(&buf[..n]);
+            }
+            h.finalize_fixed()
+        }
+        None => {
+            let mut h = CoreWrapper::<AlgorandSumhash512Core>::default();
+            loop {
+                let n = r.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                h.update(&buf[..n]);
+            }
+            h.finalize_fixed()
+        }
+    };
+
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&digest);
+    Ok(out)
+}
+
+/// Hashes `path`, treating `-` as a request to read from stdin.
+fn hash_path(path: &str, salt: Option<[u8; DIGEST_BLOCK_SIZE]>) -> io::Result<[u8; 64]> {
+    if path == "-" {
+        hash_reader(io::stdin().lock(), salt)
+    } else {
+        hash_reader(fs::File::open(path)?, salt)
+    }
+}
+
+fn print_sums(args: &Args) -> bool {
+    let mut ok = true;
+
+    if args.paths.is_empty() {
+        match hash_reader(io::stdin().lock(), args.salt) {
+            Ok(digest) => println!("{}  -", args.encoding.encode(&digest)),
+            Err(e) => {
+                eprintln!("sumhash: stdin: {}", e);
+                ok = false;
+            }
+        }
+        return ok;
+    }
+
+    for path in &args.paths {
+        match hash_path(path, args.salt) {
+            Ok(digest) => println!("{}  {}", args.encoding.encode(&digest), path),
+            Err(e) => {
+                eprintln!("sumhash: {}: {}", path, e);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
+
+/// Reads `<digest>  <path>` lines (as printed by a plain `sumhash`
+/// invocation, in the same `--encoding`) from `args.paths`, or stdin when
+/// none are given, and verifies each listed file against its expected
+/// digest.
+fn run_check(args: &Args) -> bool {
+    let sources: Vec<Box<dyn BufRead>> = if args.paths.is_empty() {
+        vec![Box::new(BufReader::new(io::stdin()))]
+    } else {
+        let opened: io::Result<Vec<Box<dyn BufRead>>> = args
+            .paths
+            .iter()
+            .map(|p| fs::File::open(p).map(|f| Box::new(BufReader::new(f)) as Box<dyn BufRead>))
+            .collect();
+        match opened {
+            Ok(sources) => sources,
+            Err(e) => {
+                eprintln!("sumhash: {}", e);
+                return false;
+            }
+        }
+    };
+
+    let mut ok = true;
+    for src in sources {
+        for line in src.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    eprintln!("sumhash: {}", e);
+                    ok = false;
+                    continue;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let Some((expected_encoded, path)) = line.split_once("  ") else {
+                eprintln!("sumhash: malformed check line: {}", line);
+                ok = false;
+                continue;
+            };
+
+            let expected = match args.encoding.decode(expected_encoded) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    eprintln!("sumhash: {}: {}", path, e);
+                    ok = false;
+                    continue;
+                }
+            };
+
+            match hash_path(path, args.salt) {
+                Ok(got) if fixed_time_eq(&got, &expected) => println!("{}: OK", path),
+                Ok(_) => {
+                    println!("{}: FAILED", path);
+                    ok = false;
+                }
+                Err(e) => {
+                    eprintln!("sumhash: {}: {}", path, e);
+                    ok = false;
+                }
+            }
+        }
+    }
+
+    ok
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("sumhash: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let ok = if args.check {
+        run_check(&args)
+    } else {
+        print_sums(&args)
+    };
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,6 +1,11 @@
-use byteorder::ReadBytesExt;
-use sha3::{digest::ExtendableOutput, Shake256};
-use std::io::Write;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+#[cfg(feature = "simd-wide")]
+use wide::u64x4;
 
 /// Matrix is the n-by-m sumhash matrix A with elements in Z_q where q=2^64.
 #[derive(Clone)]
@@ -10,7 +15,7 @@ pub struct Matrix {
 
 impl Matrix {
     /// random_matrix generates a random n x m matrix from the random source.
-    pub fn random_matrix<T: ReadBytesExt>(mut rand: T, n: usize, m: usize) -> Matrix {
+    pub fn random_matrix<T: XofReader>(mut rand: T, n: usize, m: usize) -> Matrix {
         if m % 8 != 0 {
             panic!("m={:?} is not a multiple of 8", m);
         }
@@ -19,7 +24,9 @@ impl Matrix {
         (0..n).for_each(|i| {
             matrix.push(Vec::with_capacity(m));
             (0..m).for_each(|_| {
-                matrix[i].push(rand.read_u64::<byteorder::LittleEndian>().unwrap());
+                let mut buf = [0u8; 8];
+                rand.read(&mut buf);
+                matrix[i].push(u64::from_le_bytes(buf));
             });
         });
         Matrix { matrix }
@@ -28,10 +35,10 @@ impl Matrix {
     /// n and m are the rows and columns of the matrix respectively.
     pub fn random_from_seed(seed: &[u8], n: usize, m: usize) -> Self {
         let mut xof = Shake256::default();
-        xof.write_all(&64u16.to_le_bytes()).unwrap();
-        xof.write_all(&(n as u16).to_le_bytes()).unwrap();
-        xof.write_all(&(m as u16).to_le_bytes()).unwrap();
-        xof.write_all(seed).unwrap();
+        xof.update(&64u16.to_le_bytes());
+        xof.update(&(n as u16).to_le_bytes());
+        xof.update(&(m as u16).to_le_bytes());
+        xof.update(seed);
 
         Matrix::random_matrix(xof.finalize_xof(), n, m)
     }
@@ -50,7 +57,27 @@ impl Matrix {
             });
         });
 
-        LookupTable { lookup_table: at }
+        // Column-major copy of `at`, laid out `[m/8][256][n]`: for a fixed column j
+        // and byte value b, the n row contributions are contiguous in memory. The
+        // SIMD backends gather from this layout so that, for a fixed `(j, msg[j])`,
+        // each group of 4 (AVX2, `wide`) or 2 (NEON) adjacent rows is read with one
+        // vector load. The rows are not aligned to the vector width, so these are
+        // unaligned loads.
+        let cols = m / 8;
+        let mut transposed = Vec::with_capacity(cols * 256);
+        (0..cols).for_each(|j| {
+            (0..256usize).for_each(|b| {
+                let mut lane = Vec::with_capacity(n);
+                (0..n).for_each(|i| lane.push(at[i][j][b]));
+                transposed.push(lane);
+            });
+        });
+
+        LookupTable {
+            lookup_table: at,
+            transposed,
+            backend: Backend::detect(),
+        }
     }
 }
 
@@ -73,11 +100,169 @@ fn sum_bits(a: &[u64], b: u8) -> u64 {
         .wrapping_add(a7)
 }
 
+/// Backend selects the instruction set used by `LookupTable::compress`. It is
+/// picked once, at construction. `Avx2`/`Neon` are hand-written intrinsics
+/// behind runtime CPU feature detection; `Wide` is a portable fallback built
+/// on the `wide` crate's cross-platform vector types, used on targets (or
+/// CPUs) where neither of those applies; `Scalar` is the last resort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Backend {
+    Scalar,
+    #[cfg(feature = "simd-wide")]
+    Wide,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+impl Backend {
+    // Runtime CPU feature detection (`is_x86_feature_detected!` and friends)
+    // is a `std`-only facility; `no_std` builds skip straight to `Wide` (or
+    // `Scalar`, if the `simd-wide` feature is disabled).
+    #[cfg(feature = "std")]
+    #[allow(unreachable_code)]
+    fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if std::is_x86_feature_detected!("avx2") {
+                return Backend::Avx2;
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                return Backend::Neon;
+            }
+        }
+        #[cfg(feature = "simd-wide")]
+        {
+            return Backend::Wide;
+        }
+        Backend::Scalar
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[allow(unreachable_code)]
+    fn detect() -> Self {
+        #[cfg(feature = "simd-wide")]
+        {
+            return Backend::Wide;
+        }
+        Backend::Scalar
+    }
+}
+
 /// LookupTable is the precomputed sums from a matrix for every possible byte of input.
 /// Its dimensions are `[n][m/8][256]u64`.
 #[derive(Clone)]
 pub struct LookupTable {
     lookup_table: Vec<Vec<[u64; 256]>>,
+    transposed: Vec<Vec<u64>>,
+    backend: Backend,
+}
+
+impl LookupTable {
+    fn compress_scalar(&self, dst: &mut [u8], msg: &[u8]) {
+        (0..self.lookup_table.len()).for_each(|i| {
+            let x = (0..self.lookup_table[i].len()).fold(0u64, |x, j| {
+                x.wrapping_add(self.lookup_table[i][j][msg[j] as usize])
+            });
+            dst[8 * i..8 * i + 8].clone_from_slice(&x.to_le_bytes());
+        });
+    }
+
+    /// Gathers the per-column row contribution at row `i` for the given message,
+    /// reading from the transposed (column-major) table.
+    fn column(&self, j: usize, msg: &[u8], i: usize) -> u64 {
+        self.transposed[j * 256 + msg[j] as usize][i]
+    }
+
+    /// Portable vectorized path built on `wide::u64x4`: safe code that
+    /// vectorizes on whatever the target's best available instruction set
+    /// is (falling back to a plain array internally on targets `wide`
+    /// doesn't accelerate), unlike `compress_avx2`/`compress_neon` which
+    /// hard-code one specific ISA each.
+    #[cfg(feature = "simd-wide")]
+    fn compress_wide(&self, dst: &mut [u8], msg: &[u8]) {
+        let n = self.lookup_table.len();
+        let cols = msg.len();
+        let mut i = 0;
+        while i + 4 <= n {
+            let mut acc = u64x4::splat(0);
+            for j in 0..cols {
+                let lane = &self.transposed[j * 256 + msg[j] as usize][i..i + 4];
+                acc += u64x4::new([lane[0], lane[1], lane[2], lane[3]]);
+            }
+            let out = acc.to_array();
+            for (k, word) in out.iter().enumerate() {
+                dst[8 * (i + k)..8 * (i + k) + 8].clone_from_slice(&word.to_le_bytes());
+            }
+            i += 4;
+        }
+        while i < n {
+            let x = (0..cols).fold(0u64, |x, j| x.wrapping_add(self.column(j, msg, i)));
+            dst[8 * i..8 * i + 8].clone_from_slice(&x.to_le_bytes());
+            i += 1;
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn compress_avx2(&self, dst: &mut [u8], msg: &[u8]) {
+        use core::arch::x86_64::*;
+
+        let n = self.lookup_table.len();
+        let cols = msg.len();
+        let mut i = 0;
+        while i + 4 <= n {
+            let mut acc = _mm256_setzero_si256();
+            for j in 0..cols {
+                let lane = &self.transposed[j * 256 + msg[j] as usize][i..i + 4];
+                let v = _mm256_loadu_si256(lane.as_ptr() as *const __m256i);
+                acc = _mm256_add_epi64(acc, v);
+            }
+            let mut out = [0u64; 4];
+            _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, acc);
+            for (k, word) in out.iter().enumerate() {
+                dst[8 * (i + k)..8 * (i + k) + 8].clone_from_slice(&word.to_le_bytes());
+            }
+            i += 4;
+        }
+        while i < n {
+            let x = (0..cols).fold(0u64, |x, j| x.wrapping_add(self.column(j, msg, i)));
+            dst[8 * i..8 * i + 8].clone_from_slice(&x.to_le_bytes());
+            i += 1;
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[target_feature(enable = "neon")]
+    unsafe fn compress_neon(&self, dst: &mut [u8], msg: &[u8]) {
+        use core::arch::aarch64::*;
+
+        let n = self.lookup_table.len();
+        let cols = msg.len();
+        let mut i = 0;
+        while i + 2 <= n {
+            let mut acc = vdupq_n_u64(0);
+            for j in 0..cols {
+                let lane = &self.transposed[j * 256 + msg[j] as usize][i..i + 2];
+                let v = vld1q_u64(lane.as_ptr());
+                acc = vaddq_u64(acc, v);
+            }
+            let mut out = [0u64; 2];
+            vst1q_u64(out.as_mut_ptr(), acc);
+            dst[8 * i..8 * i + 8].clone_from_slice(&out[0].to_le_bytes());
+            dst[8 * (i + 1)..8 * (i + 1) + 8].clone_from_slice(&out[1].to_le_bytes());
+            i += 2;
+        }
+        while i < n {
+            let x = (0..cols).fold(0u64, |x, j| x.wrapping_add(self.column(j, msg, i)));
+            dst[8 * i..8 * i + 8].clone_from_slice(&x.to_le_bytes());
+            i += 1;
+        }
+    }
 }
 
 /// Compressor represents the compression function which is performed on a message.
@@ -149,12 +334,15 @@ impl Compressor for LookupTable {
             )
         }
 
-        (0..self.lookup_table.len()).for_each(|i| {
-            let x = (0..self.lookup_table[i].len()).fold(0u64, |x, j| {
-                x.wrapping_add(self.lookup_table[i][j][msg[j] as usize])
-            });
-            dst[8 * i..8 * i + 8].clone_from_slice(&x.to_le_bytes());
-        });
+        match self.backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { self.compress_avx2(dst, msg) },
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon => unsafe { self.compress_neon(dst, msg) },
+            #[cfg(feature = "simd-wide")]
+            Backend::Wide => self.compress_wide(dst, msg),
+            Backend::Scalar => self.compress_scalar(dst, msg),
+        }
     }
 }
 
@@ -166,7 +354,7 @@ pub mod test {
         const N: usize = 14;
         const M: usize = N * 64 * 2;
 
-        let rand = &mut Shake256::default().finalize_xof();
+        let rand = Shake256::default().finalize_xof();
         let a = Matrix::random_matrix(rand, N, M);
         let at = a.lookup_table();
 
@@ -186,4 +374,53 @@ pub mod test {
             assert_eq!(dst1, dst2, "matrix and lookup table outputs are different");
         });
     }
+
+    #[test]
+    fn simd_backend_matches_scalar_fallback() {
+        const N: usize = 14;
+        const M: usize = N * 64 * 2;
+
+        let rand = Shake256::default().finalize_xof();
+        let a = Matrix::random_matrix(rand, N, M);
+        let at = a.lookup_table();
+
+        let mut dst_dispatch = vec![0u8; at.output_len()];
+        let mut dst_scalar = vec![0u8; at.output_len()];
+
+        (0..1000).for_each(|_| {
+            let msg: Vec<u8> = (0..at.input_len()).map(|_| rand::random::<u8>()).collect();
+            at.compress(&mut dst_dispatch, &msg);
+            at.compress_scalar(&mut dst_scalar, &msg);
+
+            assert_eq!(
+                dst_dispatch, dst_scalar,
+                "dispatched backend diverged from the scalar fallback"
+            );
+        });
+    }
+
+    #[cfg(feature = "simd-wide")]
+    #[test]
+    fn compress_wide_matches_scalar_fallback() {
+        const N: usize = 14;
+        const M: usize = N * 64 * 2;
+
+        let rand = Shake256::default().finalize_xof();
+        let a = Matrix::random_matrix(rand, N, M);
+        let at = a.lookup_table();
+
+        let mut dst_wide = vec![0u8; at.output_len()];
+        let mut dst_scalar = vec![0u8; at.output_len()];
+
+        (0..1000).for_each(|_| {
+            let msg: Vec<u8> = (0..at.input_len()).map(|_| rand::random::<u8>()).collect();
+            at.compress_wide(&mut dst_wide, &msg);
+            at.compress_scalar(&mut dst_scalar, &msg);
+
+            assert_eq!(
+                dst_wide, dst_scalar,
+                "wide backend diverged from the scalar fallback"
+            );
+        });
+    }
 }
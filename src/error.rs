@@ -0,0 +1,78 @@
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+/// `SumhashError` is the error type returned by the crate's fallible APIs.
+/// It replaces ad hoc panics with structured variants so a malformed salt or
+/// an oversized input doesn't have to abort the process.
+#[derive(Debug)]
+pub enum SumhashError {
+    /// Writing more data would overflow the internal bit-length counter.
+    LengthOverflow {
+        /// Number of bytes already written.
+        wrote: u64,
+        /// Number of additional bytes that were about to be written.
+        adding: u64,
+    },
+    /// A salt of the wrong size was supplied.
+    BadSaltSize {
+        /// The expected salt size, in bytes.
+        want: usize,
+        /// The salt size that was actually supplied.
+        got: usize,
+    },
+    /// A serialized midstate (see `Sumhash512Core::from_state`) was
+    /// truncated or otherwise malformed, or the hash state was found
+    /// inconsistent (see `sumhash::Digest::sum`).
+    Corrupted(String),
+    /// An I/O error encountered while buffering input. Only constructible
+    /// when the `std` feature is enabled.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// Failed to draw randomness from the OS CSPRNG.
+    Rng(getrandom::Error),
+}
+
+impl fmt::Display for SumhashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumhashError::LengthOverflow { wrote, adding } => write!(
+                f,
+                "length overflow: already wrote {} bytes, trying to write {} more",
+                wrote, adding
+            ),
+            SumhashError::BadSaltSize { want, got } => {
+                write!(f, "bad salt size: want {}, got {}", want, got)
+            }
+            SumhashError::Corrupted(msg) => write!(f, "corrupted sumhash state: {}", msg),
+            #[cfg(feature = "std")]
+            SumhashError::Io(e) => write!(f, "io error: {}", e),
+            SumhashError::Rng(e) => write!(f, "failed to generate random salt: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SumhashError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SumhashError::Io(e) => Some(e),
+            SumhashError::Rng(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for SumhashError {
+    fn from(e: std::io::Error) -> Self {
+        SumhashError::Io(e)
+    }
+}
+
+impl From<getrandom::Error> for SumhashError {
+    fn from(e: getrandom::Error) -> Self {
+        SumhashError::Rng(e)
+    }
+}
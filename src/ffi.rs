@@ -0,0 +1,281 @@
+//! C ABI bindings for non-Rust callers, built as a `cdylib` via the `capi`
+//! feature. Handles are opaque pointers allocated with `Box::into_raw` and
+//! must be released with `sumhash512_free` (or consumed by
+//! `sumhash512_finalize`). The header in `include/sumhash.h` is generated
+//! from this module with `cbindgen` (see `cbindgen.toml`).
+use core::{ptr, slice};
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+use digest::{core_api::CoreWrapper, FixedOutput, Reset, Update};
+
+use crate::error::SumhashError;
+use crate::sumhash512core::{
+    algorand_compressor, AlgorandSumhash512Core, Sumhash512Core, DIGEST_BLOCK_SIZE, DIGEST_SIZE,
+};
+
+/// Opaque handle to an in-progress sumhash512 computation.
+pub struct SumhashHandle(CoreWrapper<AlgorandSumhash512Core>);
+
+/// Status codes returned by the functions in this module. `0` means success;
+/// the positive codes mirror `SumhashError`'s variants; `-1` covers
+/// conditions that have no `SumhashError` counterpart, such as a null
+/// pointer.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SumhashStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A salt of the wrong size was supplied.
+    BadSaltSize = 1,
+    /// A serialized midstate was truncated or otherwise malformed, or the
+    /// hash state was found inconsistent.
+    Corrupted = 2,
+    /// Writing more data would overflow the internal bit-length counter.
+    LengthOverflow = 3,
+    /// An I/O error was encountered while buffering input.
+    Io = 4,
+    /// Failed to draw randomness from the OS CSPRNG.
+    Rng = 5,
+    /// A null pointer or other condition outside the `SumhashError` variants.
+    Unknown = -1,
+}
+
+impl From<&SumhashError> for SumhashStatus {
+    fn from(e: &SumhashError) -> Self {
+        match e {
+            SumhashError::BadSaltSize { .. } => SumhashStatus::BadSaltSize,
+            SumhashError::Corrupted(_) => SumhashStatus::Corrupted,
+            SumhashError::LengthOverflow { .. } => SumhashStatus::LengthOverflow,
+            #[cfg(feature = "std")]
+            SumhashError::Io(_) => SumhashStatus::Io,
+            SumhashError::Rng(_) => SumhashStatus::Rng,
+        }
+    }
+}
+
+/// Allocates and initializes a handle computing an unsalted sumhash512.
+/// Release it with `sumhash512_free` or `sumhash512_finalize`.
+#[no_mangle]
+pub extern "C" fn sumhash512_init() -> *mut SumhashHandle {
+    Box::into_raw(Box::new(SumhashHandle(CoreWrapper::default())))
+}
+
+/// Allocates and initializes a handle computing a salted sumhash512. `salt`
+/// must point to exactly `DIGEST_BLOCK_SIZE` (64) bytes; returns null if
+/// `salt` is null or `salt_len` isn't 64.
+#[no_mangle]
+pub extern "C" fn sumhash512_init_salted(salt: *const u8, salt_len: usize) -> *mut SumhashHandle {
+    if salt.is_null() || salt_len != DIGEST_BLOCK_SIZE {
+        return ptr::null_mut();
+    }
+    let mut s = [0u8; DIGEST_BLOCK_SIZE];
+    s.copy_from_slice(unsafe { slice::from_raw_parts(salt, salt_len) });
+    Box::into_raw(Box::new(SumhashHandle(CoreWrapper::from_core(
+        Sumhash512Core::new_with_salt(s),
+    ))))
+}
+
+/// Allocates a handle salted with a fresh OS-CSPRNG salt, writing the
+/// generated salt to `out_salt` (`DIGEST_BLOCK_SIZE`, 64, bytes) so it can be
+/// persisted and reused for verification, and the handle to `out_handle`.
+/// Returns `Rng` if the CSPRNG fails, or `Unknown` if either pointer is null.
+#[no_mangle]
+pub extern "C" fn sumhash512_init_random_salted(
+    out_handle: *mut *mut SumhashHandle,
+    out_salt: *mut u8,
+) -> SumhashStatus {
+    if out_handle.is_null() || out_salt.is_null() {
+        return SumhashStatus::Unknown;
+    }
+    match AlgorandSumhash512Core::new_with_random_salt(algorand_compressor()) {
+        Ok((core, salt)) => {
+            unsafe {
+                slice::from_raw_parts_mut(out_salt, DIGEST_BLOCK_SIZE).copy_from_slice(&salt);
+                *out_handle =
+                    Box::into_raw(Box::new(SumhashHandle(CoreWrapper::from_core(core))));
+            }
+            SumhashStatus::Ok
+        }
+        Err(e) => SumhashStatus::from(&e),
+    }
+}
+
+/// Feeds `len` bytes at `data` into `handle`. Returns `Unknown` if `handle`
+/// is null, or if `data` is null while `len` is nonzero.
+#[no_mangle]
+pub extern "C" fn sumhash512_update(
+    handle: *mut SumhashHandle,
+    data: *const u8,
+    len: usize,
+) -> SumhashStatus {
+    if handle.is_null() || (data.is_null() && len != 0) {
+        return SumhashStatus::Unknown;
+    }
+    let handle = unsafe { &mut *handle };
+    let data: &[u8] = if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(data, len) }
+    };
+    handle.0.update(data);
+    SumhashStatus::Ok
+}
+
+/// Writes the 64-byte digest to `out` and frees `handle`; `handle` must not
+/// be used again afterwards. Returns `Unknown`, without freeing `handle`, if
+/// `handle` or `out` is null.
+#[no_mangle]
+pub extern "C" fn sumhash512_finalize(handle: *mut SumhashHandle, out: *mut u8) -> SumhashStatus {
+    if handle.is_null() || out.is_null() {
+        return SumhashStatus::Unknown;
+    }
+    let handle = unsafe { Box::from_raw(handle) };
+    let digest = handle.0.finalize_fixed();
+    unsafe { slice::from_raw_parts_mut(out, DIGEST_SIZE) }.copy_from_slice(&digest);
+    SumhashStatus::Ok
+}
+
+/// Resets `handle` so it can be reused for a new computation.
+#[no_mangle]
+pub extern "C" fn sumhash512_reset(handle: *mut SumhashHandle) -> SumhashStatus {
+    if handle.is_null() {
+        return SumhashStatus::Unknown;
+    }
+    unsafe { &mut *handle }.0.reset();
+    SumhashStatus::Ok
+}
+
+/// Frees a handle without finalizing it. A no-op if `handle` is null.
+#[no_mangle]
+pub extern "C" fn sumhash512_free(handle: *mut SumhashHandle) {
+    if !handle.is_null() {
+        unsafe {
+            drop(Box::from_raw(handle));
+        }
+    }
+}
+
+/// One-shot unsalted sumhash512 of the `len` bytes at `data`, written to
+/// `out` (which must point to `DIGEST_SIZE` (64) writable bytes).
+#[no_mangle]
+pub extern "C" fn sumhash512_oneshot(data: *const u8, len: usize, out: *mut u8) -> SumhashStatus {
+    if (data.is_null() && len != 0) || out.is_null() {
+        return SumhashStatus::Unknown;
+    }
+    let data: &[u8] = if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(data, len) }
+    };
+    let mut h = CoreWrapper::<AlgorandSumhash512Core>::default();
+    h.update(data);
+    let digest = h.finalize_fixed();
+    unsafe { slice::from_raw_parts_mut(out, DIGEST_SIZE) }.copy_from_slice(&digest);
+    SumhashStatus::Ok
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn oneshot(data: &[u8]) -> [u8; DIGEST_SIZE] {
+        let mut out = [0u8; DIGEST_SIZE];
+        assert_eq!(
+            sumhash512_oneshot(data.as_ptr(), data.len(), out.as_mut_ptr()),
+            SumhashStatus::Ok
+        );
+        out
+    }
+
+    #[test]
+    fn oneshot_matches_safe_api() {
+        let mut h = CoreWrapper::<AlgorandSumhash512Core>::default();
+        h.update(b"sumhash input");
+        let want = h.finalize_fixed();
+
+        assert_eq!(oneshot(b"sumhash input").as_slice(), want.as_slice());
+    }
+
+    #[test]
+    fn init_update_finalize_round_trip_matches_oneshot() {
+        let handle = sumhash512_init();
+        assert_eq!(
+            sumhash512_update(handle, b"sumhash".as_ptr(), 7),
+            SumhashStatus::Ok
+        );
+        assert_eq!(
+            sumhash512_update(handle, b" input".as_ptr(), 6),
+            SumhashStatus::Ok
+        );
+
+        let mut got = [0u8; DIGEST_SIZE];
+        assert_eq!(
+            sumhash512_finalize(handle, got.as_mut_ptr()),
+            SumhashStatus::Ok
+        );
+
+        assert_eq!(got.as_slice(), oneshot(b"sumhash input").as_slice());
+    }
+
+    #[test]
+    fn salted_round_trip_matches_core() {
+        let salt = [0x42u8; DIGEST_BLOCK_SIZE];
+
+        let handle = sumhash512_init_salted(salt.as_ptr(), salt.len());
+        assert!(!handle.is_null());
+        assert_eq!(
+            sumhash512_update(handle, b"sumhash input".as_ptr(), 13),
+            SumhashStatus::Ok
+        );
+        let mut got = [0u8; DIGEST_SIZE];
+        assert_eq!(
+            sumhash512_finalize(handle, got.as_mut_ptr()),
+            SumhashStatus::Ok
+        );
+
+        let mut h = CoreWrapper::from_core(Sumhash512Core::new_with_salt(salt));
+        h.update(b"sumhash input");
+        let want = h.finalize_fixed();
+
+        assert_eq!(got.as_slice(), want.as_slice());
+    }
+
+    #[test]
+    fn init_salted_rejects_wrong_length_salt() {
+        let salt = [0u8; DIGEST_BLOCK_SIZE - 1];
+        assert!(sumhash512_init_salted(salt.as_ptr(), salt.len()).is_null());
+        assert!(sumhash512_init_salted(ptr::null(), DIGEST_BLOCK_SIZE).is_null());
+    }
+
+    #[test]
+    fn reset_and_free_null_handles_are_reported_or_noop() {
+        assert_eq!(sumhash512_reset(ptr::null_mut()), SumhashStatus::Unknown);
+        // Freeing a null handle must not crash.
+        sumhash512_free(ptr::null_mut());
+    }
+
+    #[test]
+    fn update_and_finalize_reject_null_pointers() {
+        assert_eq!(
+            sumhash512_update(ptr::null_mut(), b"x".as_ptr(), 1),
+            SumhashStatus::Unknown
+        );
+
+        let handle = sumhash512_init();
+        assert_eq!(
+            sumhash512_update(handle, ptr::null(), 1),
+            SumhashStatus::Unknown
+        );
+        assert_eq!(
+            sumhash512_finalize(handle, ptr::null_mut()),
+            SumhashStatus::Unknown
+        );
+        sumhash512_free(handle);
+
+        assert_eq!(
+            sumhash512_finalize(ptr::null_mut(), [0u8; DIGEST_SIZE].as_mut_ptr()),
+            SumhashStatus::Unknown
+        );
+    }
+}
@@ -1,4 +1,5 @@
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! This repository contains a Rust implementation of subset-sum hash function designed by the Algorand project.
 //!
 //! The reference implementation is written in Go and can be found in the [`go-sumhash`] repository.
@@ -14,6 +15,9 @@
 //!
 //! This library **isn't** audited or ready for production use, nor is it an official implementation.
 //!
+//! The default `std` feature can be disabled for `no_std` use (embedded, WASM); the
+//! `alloc` crate is still required for the `Vec`-backed matrix and lookup-table storage.
+//!
 //! [`go-sumhash`]: https://github.com/algorand/go-sumhash
 //! [`spec`]: https://github.com/algorand/go-sumhash/blob/master/spec/sumhash-spec.pdf
 //!
@@ -44,7 +48,31 @@
 //! println!("Result: {}", hex::encode(&output));
 //! ```
 //!
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 /// compress represents the compression function which is performed on a message.
 pub mod compress;
+/// error contains the crate's structured error type.
+pub mod error;
+/// ffi exposes a C ABI over `AlgorandSumhash512Core`, for building a `cdylib`
+/// consumed by non-Rust callers. Gated behind the `capi` feature.
+#[cfg(feature = "capi")]
+pub mod ffi;
+/// mac implements the RustCrypto `Mac`/`KeyInit` traits over the salted
+/// sumhash512 mode.
+pub mod mac;
+/// sumhash is the generic, lower-level streaming `Digest<C>` API that
+/// `sumhash512core`'s `Sumhash512Core` is built on top of. Needs the `std`
+/// feature for its `std::io::Write`-based buffering.
+#[cfg(feature = "std")]
+pub mod sumhash;
+/// sumhash512 is a convenience constructor for the Algorand-configured
+/// `Digest<LookupTable>`; see `sumhash`.
+#[cfg(feature = "std")]
+pub mod sumhash512;
 /// sumhash512core is a sumhash core implementation for 512 bit output.
 pub mod sumhash512core;
+/// util contains helpers that don't belong to a specific hash implementation,
+/// such as constant-time comparison for verifying digests.
+pub mod util;
@@ -0,0 +1,100 @@
+use digest::{
+    core_api::CoreWrapper,
+    crypto_common::{InvalidLength, Key, KeyInit, KeySizeUser},
+    typenum::U64,
+    FixedOutput, MacMarker, Output, OutputSizeUser, Reset, Update,
+};
+
+use crate::sumhash512core::{AlgorandSumhash512Core, Sumhash512Core, DIGEST_BLOCK_SIZE};
+
+/// `Sumhash512Mac` treats the 64-byte sumhash512 salt as a MAC key, so the
+/// salted mode can be reached through the standard RustCrypto
+/// `KeyInit`/`Mac` traits instead of only via `new_with_salt`.
+pub struct Sumhash512Mac(CoreWrapper<AlgorandSumhash512Core>);
+
+impl KeySizeUser for Sumhash512Mac {
+    type KeySize = U64;
+}
+
+impl KeyInit for Sumhash512Mac {
+    fn new(key: &Key<Self>) -> Self {
+        let mut salt = [0u8; DIGEST_BLOCK_SIZE];
+        salt.copy_from_slice(key);
+        Self(CoreWrapper::from_core(Sumhash512Core::new_with_salt(salt)))
+    }
+
+    fn new_from_slice(key: &[u8]) -> Result<Self, InvalidLength> {
+        if key.len() != DIGEST_BLOCK_SIZE {
+            return Err(InvalidLength);
+        }
+        let mut salt = [0u8; DIGEST_BLOCK_SIZE];
+        salt.copy_from_slice(key);
+        Ok(Self(CoreWrapper::from_core(Sumhash512Core::new_with_salt(
+            salt,
+        ))))
+    }
+}
+
+impl Update for Sumhash512Mac {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+}
+
+impl OutputSizeUser for Sumhash512Mac {
+    type OutputSize = U64;
+}
+
+impl FixedOutput for Sumhash512Mac {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        self.0.finalize_into(out);
+    }
+}
+
+impl Reset for Sumhash512Mac {
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+impl MacMarker for Sumhash512Mac {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use digest::Mac;
+
+    #[test]
+    fn mac_matches_salted_core() {
+        let salt = [0x42; DIGEST_BLOCK_SIZE];
+
+        let mut mac = <Sumhash512Mac as Mac>::new_from_slice(&salt).unwrap();
+        Mac::update(&mut mac, b"sumhash input");
+        let tag = mac.finalize().into_bytes();
+
+        let mut h = CoreWrapper::from_core(Sumhash512Core::new_with_salt(salt));
+        h.update(b"sumhash input");
+        let digest = h.finalize_fixed();
+
+        assert_eq!(tag.as_slice(), digest.as_slice());
+    }
+
+    #[test]
+    fn verify_rejects_wrong_tag() {
+        let salt = [0x7; DIGEST_BLOCK_SIZE];
+
+        let mut mac = <Sumhash512Mac as Mac>::new_from_slice(&salt).unwrap();
+        Mac::update(&mut mac, b"sumhash input");
+        let mut wrong_tag = mac.finalize().into_bytes();
+        wrong_tag[0] ^= 0xff;
+
+        let mut mac = <Sumhash512Mac as Mac>::new_from_slice(&salt).unwrap();
+        Mac::update(&mut mac, b"sumhash input");
+        assert!(mac.verify(&wrong_tag).is_err());
+    }
+
+    #[test]
+    fn new_from_slice_rejects_wrong_key_size() {
+        assert!(<Sumhash512Mac as Mac>::new_from_slice(&[0u8; 16]).is_err());
+    }
+}
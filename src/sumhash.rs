@@ -1,5 +1,5 @@
 use crate::compress;
-use anyhow::Result;
+use crate::error::SumhashError;
 use byteorder::{ByteOrder, LittleEndian};
 use std::io::Write;
 
@@ -22,7 +22,7 @@ impl<C: compress::Compressor> Digest<C> {
     /// Returns a `Digest` with the specified `Compressor`.
     /// If salt is `None`, the hash is calculated in unsalted mode.
     /// Otherwise, salt should be `BlockSize(c)` bytes, and the hash is computed in salted mode.
-    pub fn new(c: C, salt: Option<Vec<u8>>) -> Result<Digest<C>> {
+    pub fn new(c: C, salt: Option<Vec<u8>>) -> Result<Digest<C>, SumhashError> {
         let output_len = c.output_len();
         let input_len = c.input_len();
 
@@ -41,7 +41,10 @@ impl<C: compress::Compressor> Digest<C> {
 
         if let Some(ref salt) = d.salt {
             if salt.len() != d.block_size {
-                panic!("bad salt size: want {}, got {}", d.block_size, salt.len())
+                return Err(SumhashError::BadSaltSize {
+                    want: d.block_size,
+                    got: salt.len(),
+                });
             }
         }
 
@@ -50,6 +53,20 @@ impl<C: compress::Compressor> Digest<C> {
         Ok(d)
     }
 
+    /// `new_with_random_salt` draws a fresh `block_size(c)`-byte salt from the
+    /// OS CSPRNG and returns a `Digest` computing in salted mode, together
+    /// with the generated salt so it can be stored and reused for
+    /// verification. Because the CSPRNG can fail, this returns a `Result`
+    /// instead of panicking.
+    pub fn new_with_random_salt(c: C) -> Result<(Digest<C>, Vec<u8>), SumhashError> {
+        let block_size = c.input_len() - c.output_len();
+        let mut salt = vec![0u8; block_size];
+        getrandom::getrandom(&mut salt)?;
+
+        let d = Digest::new(c, Some(salt.clone()))?;
+        Ok((d, salt))
+    }
+
     /// `reset` resets the state of the hash so it can be reused.
     pub fn reset(&mut self) {
         self.h.iter_mut().for_each(|a| *a = 0);
@@ -74,15 +91,15 @@ impl<C: compress::Compressor> Digest<C> {
     }
 
     /// `write` adds more data to the running hash.
-    pub fn write(&mut self, mut p: &[u8]) -> Result<usize> {
+    pub fn write(&mut self, mut p: &[u8]) -> Result<usize, SumhashError> {
         let nn = p.len();
 
         // Check if the new length (in bits) overflows our counter capacity.
         if nn as u64 >= (1 << 61) - self.len {
-            panic!(
-                "length overflow: already wrote {} bytes, trying to write {} bytes",
-                self.len, nn
-            );
+            return Err(SumhashError::LengthOverflow {
+                wrote: self.len,
+                adding: nn as u64,
+            });
         }
 
         self.len += nn as u64;
@@ -92,7 +109,7 @@ impl<C: compress::Compressor> Digest<C> {
 
             self.nx += n;
             if self.nx == self.block_size {
-                blocks(self, &self.x.clone());
+                blocks(self, &self.x.clone())?;
                 self.nx = 0
             }
             p = &p[n..];
@@ -101,15 +118,12 @@ impl<C: compress::Compressor> Digest<C> {
         if p.len() >= self.block_size {
             // handle any remaining full input blocks
             let n = p.len() / self.block_size * self.block_size;
-            blocks(self, &p[..n]);
+            blocks(self, &p[..n])?;
             p = &p[n..];
         }
         if !p.is_empty() {
             // handle any remaining input
-            match self.x.as_mut_slice().write(p) {
-                Ok(s) => self.nx = s,
-                Err(_) => panic!("copying data"),
-            }
+            self.nx = self.x.as_mut_slice().write(p)?;
         }
 
         Ok(nn)
@@ -131,7 +145,7 @@ impl<C: compress::Compressor> Digest<C> {
 
     /// `sum` appends the current hash to b and returns the resulting slice.
     /// It does not change the underlying hash state.
-    pub fn sum(&self, mut iin: Vec<u8>) -> Result<Vec<u8>> {
+    pub fn sum(&self, mut iin: Vec<u8>) -> Result<Vec<u8>, SumhashError> {
         // TODO(jsign): receiving Vec<u8> might not be ideal.
         // Make a copy of d so that caller can keep writing and summing.
         let mut d0 = self.copy();
@@ -140,7 +154,7 @@ impl<C: compress::Compressor> Digest<C> {
         Ok(iin)
     }
 
-    fn check_sum(&mut self) -> Result<Vec<u8>> {
+    fn check_sum(&mut self) -> Result<Vec<u8>, SumhashError> {
         let b = self.block_size;
         let p = b - 16;
 
@@ -163,21 +177,24 @@ impl<C: compress::Compressor> Digest<C> {
         self.write(&tmp[0..16])?;
 
         if self.nx != 0 {
-            // buffer must be empty now
-            panic!("d.nx != 0")
+            // The padding above is sized to always land exactly on a block
+            // boundary; reaching this means the padding math has a bug.
+            return Err(SumhashError::Corrupted(
+                "internal buffer not empty after padding".to_string(),
+            ));
         }
 
         Ok(self.h.clone())
     }
 }
 // `blocks` hashes full blocks of data. len(data) must be a multiple of d.blockSize.
-fn blocks<C: compress::Compressor>(d: &mut Digest<C>, data: &[u8]) {
+fn blocks<C: compress::Compressor>(d: &mut Digest<C>, data: &[u8]) -> Result<(), SumhashError> {
     let mut cin = vec![0u8; d.c.input_len()];
 
     (0..data.len() - d.block_size + 1)
         .step_by(d.block_size)
-        .for_each(|i| {
-            cin[0..d.size].as_mut().write_all(&d.h).unwrap();
+        .try_for_each(|i| -> Result<(), SumhashError> {
+            cin[0..d.size].as_mut().write_all(&d.h)?;
 
             let input = &data[i..i + d.block_size];
             if let Some(ref salt) = d.salt {
@@ -185,11 +202,11 @@ fn blocks<C: compress::Compressor>(d: &mut Digest<C>, data: &[u8]) {
             } else {
                 cin[d.size..d.size + d.block_size]
                     .as_mut()
-                    .write_all(input)
-                    .unwrap();
+                    .write_all(input)?;
             }
 
             d.c.compress(&mut d.h, &cin);
+            Ok(())
         })
 }
 
@@ -222,7 +239,7 @@ pub mod test {
             "fc91828801365750f0267edd5530a301d1471736c485472bbadf22507731a81fd67e0d80cce722a81c6dc690b698f5771713855c5d1927488d79713e3abd81053de2c7db1430b8fb106b3f6aa6b93e54aec351e47bcc176c0df58a0336d24979a064f3acb67a693db399c6402149157b"
             ];
 
-        let a = compress::random_matrix_from_seed(&[0x11, 0x22, 0x33, 0x44], 14, 14 * 64 * 4);
+        let a = Matrix::random_from_seed(&[0x11, 0x22, 0x33, 0x44], 14, 14 * 64 * 4);
         let a_t = a.lookup_table();
 
         let mut h1 = Digest::new(a, None)?;
@@ -254,7 +271,7 @@ pub mod test {
 
     #[test]
     fn hash_custom() -> Result<()> {
-        let a = compress::random_matrix_from_seed(&[0x11, 0x22, 0x33, 0x44], 14, 14 * 64 * 4);
+        let a = Matrix::random_from_seed(&[0x11, 0x22, 0x33, 0x44], 14, 14 * 64 * 4);
 
         let mut h1 = Digest::new(a, None)?;
 
@@ -270,8 +287,8 @@ pub mod test {
     }
 
     fn test_hash_params(n: usize, m: usize) -> Result<()> {
-        let mut rand = Shake256::default().finalize_xof();
-        let a = compress::random_matrix(&mut rand, n, m);
+        let rand = Shake256::default().finalize_xof();
+        let a = Matrix::random_matrix(rand, n, m);
         let a_t = a.lookup_table();
 
         let input_len = a.input_len();
@@ -291,9 +308,10 @@ pub mod test {
             "h2 has unexpected block size"
         );
 
+        let mut msg_rand = Shake256::default().finalize_xof();
         for l in [1, 64, 100, 128, input_len, 6000, 6007] {
             let mut msg = vec![0; l];
-            rand.read_exact(&mut msg)?;
+            msg_rand.read_exact(&mut msg)?;
 
             h1.write(&msg)?;
             h2.write(&msg)?;
@@ -6,9 +6,12 @@ use digest::{
     HashMarker, Output, OutputSizeUser, Reset,
 };
 
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec::Vec};
 use byteorder::{ByteOrder, LittleEndian};
 
 use crate::compress::{Compressor, LookupTable, Matrix};
+use crate::error::SumhashError;
 
 /// The size in bytes of the sumhash checksum.
 pub const DIGEST_SIZE: usize = 64;
@@ -33,17 +36,31 @@ impl AlgorandSumhash512Core {
 
 impl Default for AlgorandSumhash512Core {
     fn default() -> Self {
-        let c = Matrix::random_from_seed("Algorand".as_bytes(), 8, 1024);
-        Sumhash512Core::new(c.lookup_table(), None)
+        Sumhash512Core::new(algorand_compressor(), None)
     }
 }
 
+/// The lookup table `AlgorandSumhash512Core` is built on: the Algorand-seeded
+/// matrix, derived once per call the same way `Default` does. Callers that
+/// need an `AlgorandSumhash512Core` via the generic `Sumhash512Core<C>` API
+/// (e.g. `new_with_random_salt`) use this instead of duplicating an
+/// Algorand-specific inherent method that would collide with the generic one.
+pub(crate) fn algorand_compressor() -> LookupTable {
+    Matrix::random_from_seed("Algorand".as_bytes(), 8, 1024).lookup_table()
+}
+
 /// Sumhash512Core returns a core implementation for sumhash cryptographic hash function.
 pub struct Sumhash512Core<C: Compressor> {
     c: C,
     h: [u8; DIGEST_SIZE], // hash chain (from last compression, or IV)
     len: u64,
     salt: Option<[u8; DIGEST_BLOCK_SIZE]>,
+
+    // Bytes written since the last full block, used by `write`/`finalize`/
+    // `export_state` so a midstate can be checkpointed independently of the
+    // block buffer `CoreWrapper` keeps for the `digest::Update` path.
+    buf: [u8; DIGEST_BLOCK_SIZE],
+    buf_len: usize,
 }
 
 impl<C: Compressor> Sumhash512Core<C> {
@@ -53,9 +70,165 @@ impl<C: Compressor> Sumhash512Core<C> {
             h: [0; DIGEST_SIZE],
             salt,
             len: 0,
+            buf: [0; DIGEST_BLOCK_SIZE],
+            buf_len: 0,
+        }
+    }
+
+    /// `new_with_random_salt` draws a fresh `DIGEST_BLOCK_SIZE`-byte salt
+    /// from the OS CSPRNG and returns a `Sumhash512Core<C>` salted with it,
+    /// together with the generated salt so it can be stored and reused for
+    /// verification. Generic over the compressor, the same way `from_state`
+    /// takes `c` as a parameter rather than assuming the Algorand default.
+    /// Because the CSPRNG can fail, this returns a `Result` instead of
+    /// panicking.
+    pub fn new_with_random_salt(c: C) -> Result<(Self, [u8; DIGEST_BLOCK_SIZE]), SumhashError> {
+        let mut salt = [0u8; DIGEST_BLOCK_SIZE];
+        getrandom::getrandom(&mut salt)?;
+        let mut s = Self::new(c, Some(salt));
+        s.compress_block(&[0; DIGEST_SIZE]);
+        Ok((s, salt))
+    }
+
+    /// `write` feeds more data into the hash state, buffering any bytes that
+    /// don't yet fill a `DIGEST_BLOCK_SIZE` block. Prefer this (together with
+    /// `finalize`) over `digest::Update`/`CoreWrapper` when the midstate needs
+    /// to be checkpointed with `export_state` and resumed later, since the
+    /// leftover bytes live on `self` rather than in a buffer `CoreWrapper`
+    /// owns.
+    pub fn write(&mut self, mut data: &[u8]) {
+        if self.buf_len > 0 {
+            let n = core::cmp::min(DIGEST_BLOCK_SIZE - self.buf_len, data.len());
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&data[..n]);
+            self.buf_len += n;
+            data = &data[n..];
+            if self.buf_len == DIGEST_BLOCK_SIZE {
+                let block = self.buf;
+                self.compress_block(&block);
+                self.buf_len = 0;
+            }
+        }
+
+        while data.len() >= DIGEST_BLOCK_SIZE {
+            self.compress_block(&data[..DIGEST_BLOCK_SIZE]);
+            data = &data[DIGEST_BLOCK_SIZE..];
+        }
+
+        if !data.is_empty() {
+            self.buf[..data.len()].copy_from_slice(data);
+            self.buf_len = data.len();
         }
     }
 
+    /// `finalize` pads and compresses any buffered bytes and returns the
+    /// resulting digest, following the same padding scheme as
+    /// `FixedOutputCore::finalize_fixed_core`.
+    pub fn finalize(&mut self) -> [u8; DIGEST_SIZE] {
+        let b = DIGEST_BLOCK_SIZE as u64;
+        let p = DIGEST_BLOCK_SIZE - 16;
+        let total = self.len + self.buf_len as u64;
+        let bitlen = total << 3;
+
+        let mut tmp = [0u8; DIGEST_BLOCK_SIZE];
+        tmp[0] = 0x01;
+        if total % b < p as u64 {
+            self.write(&tmp[0..p - (total % b) as usize]);
+        } else {
+            self.write(&tmp[0..DIGEST_BLOCK_SIZE + p - (total % b) as usize]);
+        }
+
+        LittleEndian::write_u64(&mut tmp[0..], bitlen);
+        LittleEndian::write_u64(&mut tmp[8..], 0);
+        self.write(&tmp[0..16]);
+
+        self.h
+    }
+
+    /// `export_state` serializes the full internal state needed to resume
+    /// hashing later: the chaining value, the total message-length counter,
+    /// the salt (if any), and any bytes buffered since the last full block.
+    /// Importing the result with `from_state` reproduces bit-identical
+    /// output to an uninterrupted run.
+    pub fn export_state(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 + 2 * DIGEST_BLOCK_SIZE + DIGEST_SIZE + 8);
+        out.extend_from_slice(&self.h);
+        out.extend_from_slice(&self.len.to_le_bytes());
+        match self.salt {
+            Some(ref salt) => {
+                out.push(1);
+                out.extend_from_slice(salt);
+            }
+            None => out.push(0),
+        }
+        out.push(self.buf_len as u8);
+        out.extend_from_slice(&self.buf[..self.buf_len]);
+        out
+    }
+
+    /// `from_state` reconstructs a `Sumhash512Core` previously checkpointed
+    /// with `export_state`. The compressor `c` is not part of the serialized
+    /// state (it is derived from the matrix seed) and must be supplied by the
+    /// caller, the same way it is supplied to `new`.
+    pub fn from_state(c: C, data: &[u8]) -> Result<Self, SumhashError> {
+        if data.len() < DIGEST_SIZE + 8 + 1 {
+            return Err(SumhashError::Corrupted(
+                format!("midstate too short: got {} bytes", data.len()),
+            ));
+        }
+
+        let mut h = [0u8; DIGEST_SIZE];
+        h.copy_from_slice(&data[..DIGEST_SIZE]);
+
+        let mut len_bytes = [0u8; 8];
+        len_bytes.copy_from_slice(&data[DIGEST_SIZE..DIGEST_SIZE + 8]);
+        let len = u64::from_le_bytes(len_bytes);
+
+        let mut pos = DIGEST_SIZE + 8;
+        let has_salt = data[pos];
+        pos += 1;
+        let salt = match has_salt {
+            0 => None,
+            1 => {
+                if data.len() < pos + DIGEST_BLOCK_SIZE {
+                    return Err(SumhashError::Corrupted("truncated salt".to_string()));
+                }
+                let mut salt = [0u8; DIGEST_BLOCK_SIZE];
+                salt.copy_from_slice(&data[pos..pos + DIGEST_BLOCK_SIZE]);
+                pos += DIGEST_BLOCK_SIZE;
+                Some(salt)
+            }
+            _ => {
+                return Err(SumhashError::Corrupted(
+                    format!("invalid salt marker {}", has_salt),
+                ))
+            }
+        };
+
+        if data.len() <= pos {
+            return Err(SumhashError::Corrupted(
+                "missing buffered-bytes length".to_string(),
+            ));
+        }
+        let buf_len = data[pos] as usize;
+        pos += 1;
+        if buf_len > DIGEST_BLOCK_SIZE || data.len() < pos + buf_len {
+            return Err(SumhashError::Corrupted(
+                format!("invalid buffered-bytes length {}", buf_len),
+            ));
+        }
+        let mut buf = [0u8; DIGEST_BLOCK_SIZE];
+        buf[..buf_len].copy_from_slice(&data[pos..pos + buf_len]);
+
+        Ok(Self {
+            c,
+            h,
+            len,
+            salt,
+            buf,
+            buf_len,
+        })
+    }
+
     fn compress_block(&mut self, data: &[u8]) {
         let mut cin = [0; DIGEST_BLOCK_SIZE * 2];
         self.len += data.len() as u64;
@@ -181,6 +354,21 @@ pub mod test {
         })
     }
 
+    #[test]
+    fn new_with_random_salt_is_usable_and_verifiable() -> Result<(), SumhashError> {
+        let (core, salt) = AlgorandSumhash512Core::new_with_random_salt(algorand_compressor())?;
+        let mut h = CoreWrapper::from_core(core);
+        h.update(b"sumhash input");
+        let sum = h.finalize_fixed();
+
+        // Reconstructing with the returned salt must reproduce the digest.
+        let mut h2 = CoreWrapper::from_core(Sumhash512Core::new_with_salt(salt));
+        h2.update(b"sumhash input");
+        assert_eq!(sum, h2.finalize_fixed());
+
+        Ok(())
+    }
+
     #[test]
     fn sumhash512() {
         let mut input = [0; 6000];
@@ -239,4 +427,27 @@ pub mod test {
         let expected_sum = "43dc59ca43da473a3976a952f1c33a2b284bf858894ef7354b8fc0bae02b966391070230dd23e0713eaf012f7ad525f198341000733aa87a904f7053ce1a43c6";
         assert_eq!(sum, expected_sum, "got {}, want {}", sum, expected_sum);
     }
+
+    #[test]
+    fn resumable_midstate_roundtrip() -> Result<(), SumhashError> {
+        let mut input = [0; 6000];
+        let mut v = Shake256::default();
+        v.write_all("sumhash input".as_bytes()).unwrap();
+        v.finalize_xof().read(&mut input);
+
+        let mut h = AlgorandSumhash512Core::default();
+        h.write(&input[..3000]);
+
+        let state = h.export_state();
+
+        let c = Matrix::random_from_seed("Algorand".as_bytes(), 8, 1024).lookup_table();
+        let mut resumed = Sumhash512Core::from_state(c, &state)?;
+        resumed.write(&input[3000..]);
+
+        let sum = hex::encode(resumed.finalize());
+        let expected_sum = "43dc59ca43da473a3976a952f1c33a2b284bf858894ef7354b8fc0bae02b966391070230dd23e0713eaf012f7ad525f198341000733aa87a904f7053ce1a43c6";
+        assert_eq!(sum, expected_sum, "got {}, want {}", sum, expected_sum);
+
+        Ok(())
+    }
 }
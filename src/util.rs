@@ -0,0 +1,51 @@
+use core::ptr;
+
+/// `fixed_time_eq` compares two byte slices in time that depends only on
+/// their length, not their contents. Algorand state proofs verify a freshly
+/// computed sumhash digest against an expected one; comparing with `==` on
+/// `Vec<u8>`/`[u8]` can return as soon as a mismatching byte is found, which
+/// leaks information about the digest through a timing side channel. This
+/// requires the two slices to have equal length, and ORs together `a[i] ^
+/// b[i]` across every byte through `read_volatile` so the compiler cannot
+/// prove the loop can be short-circuited. The `--check` mode of the
+/// `sumhash` CLI uses this. `mac::Sumhash512Mac::verify` doesn't need it: the
+/// `digest::Mac` trait it implements already compares tags in constant time
+/// internally via `subtle`.
+pub fn fixed_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff: u8 = 0;
+    for i in 0..a.len() {
+        // SAFETY: `i` is in bounds for both slices since `a.len() == b.len()`.
+        let x = unsafe { ptr::read_volatile(a.as_ptr().add(i)) };
+        let y = unsafe { ptr::read_volatile(b.as_ptr().add(i)) };
+        diff |= x ^ y;
+    }
+
+    // Round-trip the accumulator through a volatile write/read so the final
+    // comparison can't be hoisted into the loop above and short-circuited.
+    unsafe {
+        ptr::write_volatile(&mut diff, diff);
+        ptr::read_volatile(&diff) == 0
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn equal_slices() {
+        assert!(fixed_time_eq(b"sumhash", b"sumhash"));
+        assert!(fixed_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn different_slices() {
+        assert!(!fixed_time_eq(b"sumhash", b"SUMHASH"));
+        assert!(!fixed_time_eq(&[0u8; 64], &[0u8; 63]));
+        assert!(!fixed_time_eq(b"abc", b"abd"));
+    }
+}